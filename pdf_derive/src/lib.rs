@@ -0,0 +1,130 @@
+//! `#[derive(Object)]` - generates `Object::serialize` and `FromPrimitive::from_primitive`
+//! for a plain struct, so it can be written to and read back from a PDF `Dictionary` without
+//! hand-written `as_*`/`into_*` boilerplate.
+extern crate proc_macro;
+extern crate syn;
+#[macro_use]
+extern crate quote;
+
+use proc_macro::TokenStream;
+use syn::{Data, DeriveInput, Fields, Type};
+
+#[proc_macro_derive(Object)]
+pub fn object_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).expect("could not parse struct");
+    let name = &input.ident;
+
+    let fields = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => panic!("#[derive(Object)] only supports structs with named fields"),
+        },
+        _ => panic!("#[derive(Object)] can only be used on structs"),
+    };
+
+    let field_name: Vec<_> = fields.iter().map(|f| f.ident.clone().unwrap()).collect();
+    let pdf_name: Vec<_> = field_name.iter().map(|i| pascal_case(&i.to_string())).collect();
+    let is_optional: Vec<_> = fields.iter().map(|f| is_option(&f.ty)).collect();
+
+    let serialize_fields = field_name.iter().zip(pdf_name.iter()).map(|(field, key)| {
+        quote! {
+            write!(out, "/{} ", #key)?;
+            Object::serialize(&self.#field, out)?;
+        }
+    });
+
+    let from_primitive_fields = field_name.iter().zip(pdf_name.iter()).zip(is_optional.iter())
+        .map(|((field, key), &optional)| {
+            // Missing required keys are reported directly instead of being pushed through
+            // `FromPrimitive` on `Primitive::Null` - only `Option<T>` accepts a missing key.
+            let missing = if optional {
+                quote! { None }
+            } else {
+                quote! {
+                    return Err(format!("Object {}, Key {} not found", stringify!(#name), #key).into())
+                }
+            };
+            quote! {
+                #field: match dict.0.remove(#key) {
+                    Some(p) => FromPrimitive::from_primitive(p, resolve)?,
+                    None => #missing,
+                },
+            }
+        });
+
+    let expanded = quote! {
+        impl Object for #name {
+            fn serialize<W: ::std::io::Write>(&self, out: &mut W) -> ::std::io::Result<()> {
+                write!(out, "<<")?;
+                #( #serialize_fields )*
+                write!(out, ">>")
+            }
+        }
+
+        impl FromPrimitive for #name {
+            fn from_primitive(p: Primitive, resolve: &Resolve) -> Result<Self> {
+                let mut dict = p.into_dictionary()?;
+                Ok(#name {
+                    #( #from_primitive_fields )*
+                })
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+/// Whether `ty` is (syntactically) `Option<...>` - used to decide whether a missing dictionary
+/// key is tolerated (falls back to `None`) or reported as an error.
+fn is_option(ty: &Type) -> bool {
+    match *ty {
+        Type::Path(ref p) => p.path.segments.last()
+            .map(|seg| seg.value().ident == "Option")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn pascal_case(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut upper_next = true;
+    for c in field.chars() {
+        if c == '_' {
+            upper_next = true;
+            continue;
+        }
+        if upper_next {
+            out.extend(c.to_uppercase());
+            upper_next = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_option_matches_option_types_only() {
+        let ty: Type = syn::parse_str("Option<i32>").unwrap();
+        assert!(is_option(&ty));
+
+        let ty: Type = syn::parse_str("std::option::Option<String>").unwrap();
+        assert!(is_option(&ty));
+
+        let ty: Type = syn::parse_str("i32").unwrap();
+        assert!(!is_option(&ty));
+
+        let ty: Type = syn::parse_str("Vec<Option<i32>>").unwrap();
+        assert!(!is_option(&ty));
+    }
+
+    #[test]
+    fn pascal_case_converts_snake_case_field_names() {
+        assert_eq!(pascal_case("width"), "Width");
+        assert_eq!(pascal_case("media_box"), "MediaBox");
+    }
+}
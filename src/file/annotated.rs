@@ -0,0 +1,97 @@
+use std::io::Write;
+use std::fmt::{Display, Formatter};
+
+use file::object::{Object, Primitive};
+
+/// A `Primitive` together with side-band metadata gathered while parsing it, e.g. its source
+/// byte range. Only this top-level value carries annotations - `Primitive::Array`/`Dictionary`
+/// entries are plain `Primitive`s, so a sub-value's own span isn't tracked.
+#[derive(Clone, Debug)]
+pub struct Annotated {
+    annotations: Vec<Primitive>,
+    value: Primitive,
+}
+
+impl Annotated {
+    pub fn new(value: Primitive) -> Annotated {
+        Annotated {annotations: vec![], value}
+    }
+
+    pub fn with_annotations(value: Primitive, annotations: Vec<Primitive>) -> Annotated {
+        Annotated {annotations, value}
+    }
+
+    /// Attaches the byte range `[start, end)` the value was parsed from.
+    pub fn with_span(value: Primitive, start: usize, end: usize) -> Annotated {
+        Annotated::with_annotations(value, vec![
+            Primitive::Integer (start as i32),
+            Primitive::Integer (end as i32),
+        ])
+    }
+
+    pub fn value(&self) -> &Primitive {
+        &self.value
+    }
+
+    pub fn value_owned(self) -> Primitive {
+        self.value
+    }
+
+    pub fn annotations(&self) -> &[Primitive] {
+        &self.annotations
+    }
+
+    /// Discards the annotations, keeping the plain `Primitive`.
+    pub fn strip_annotations(self) -> Primitive {
+        self.value
+    }
+}
+
+impl Object for Annotated {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        // Annotations are parser-side metadata only; serialized output is unaffected by them.
+        Object::serialize(&self.value, out)
+    }
+}
+
+impl Display for Annotated {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        Display::fmt(&self.value, f)
+    }
+}
+
+impl From<Primitive> for Annotated {
+    fn from(value: Primitive) -> Annotated {
+        Annotated::new(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_span_round_trips_value_and_annotations() {
+        let annotated = Annotated::with_span(Primitive::Integer (42), 10, 15);
+
+        match *annotated.value() {
+            Primitive::Integer (n) => assert_eq!(n, 42),
+            ref p => panic!("expected Integer, got {:?}", p),
+        }
+        let annotations = annotated.annotations();
+        assert_eq!(annotations.len(), 2);
+        match annotations[0] {
+            Primitive::Integer (start) => assert_eq!(start, 10),
+            ref p => panic!("expected Integer, got {:?}", p),
+        }
+        match annotations[1] {
+            Primitive::Integer (end) => assert_eq!(end, 15),
+            ref p => panic!("expected Integer, got {:?}", p),
+        }
+
+        match annotated.strip_annotations() {
+            Primitive::Integer (n) => assert_eq!(n, 42),
+            p => panic!("expected Integer, got {:?}", p),
+        }
+    }
+}
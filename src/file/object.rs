@@ -12,6 +12,113 @@ pub trait Object {
     fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()>;
 }
 
+/// Lets a `FromPrimitive` impl follow a `Primitive::Reference` back to its target.
+pub trait Resolve {
+    fn resolve(&self, r: ObjectId) -> Result<Primitive>;
+}
+
+/// Symmetric counterpart to `Object`: builds a typed value back out of a `Primitive`.
+pub trait FromPrimitive: Sized {
+    fn from_primitive(p: Primitive, resolve: &Resolve) -> Result<Self>;
+}
+
+impl FromPrimitive for Primitive {
+    fn from_primitive(p: Primitive, _resolve: &Resolve) -> Result<Self> {
+        Ok(p)
+    }
+}
+impl FromPrimitive for i32 {
+    fn from_primitive(p: Primitive, _resolve: &Resolve) -> Result<Self> {
+        p.as_integer()
+    }
+}
+impl FromPrimitive for f32 {
+    fn from_primitive(p: Primitive, _resolve: &Resolve) -> Result<Self> {
+        match p {
+            Primitive::Number (n) => Ok(n),
+            Primitive::Integer (n) => Ok(n as f32),
+            _ => Err (ErrorKind::WrongObjectType {expected: "Number", found: p.type_str()}.into())
+        }
+    }
+}
+impl FromPrimitive for bool {
+    fn from_primitive(p: Primitive, _resolve: &Resolve) -> Result<Self> {
+        match p {
+            Primitive::Boolean (b) => Ok(b),
+            _ => Err (ErrorKind::WrongObjectType {expected: "Boolean", found: p.type_str()}.into())
+        }
+    }
+}
+impl FromPrimitive for Dictionary {
+    fn from_primitive(p: Primitive, _resolve: &Resolve) -> Result<Self> {
+        p.into_dictionary()
+    }
+}
+impl<T: FromPrimitive> FromPrimitive for Option<T> {
+    fn from_primitive(p: Primitive, resolve: &Resolve) -> Result<Self> {
+        match p {
+            Primitive::Null => Ok(None),
+            p => Ok(Some(T::from_primitive(p, resolve)?)),
+        }
+    }
+}
+impl<T: FromPrimitive> FromPrimitive for Vec<T> {
+    fn from_primitive(p: Primitive, resolve: &Resolve) -> Result<Self> {
+        p.into_array()?.into_iter()
+            .map(|x| T::from_primitive(x, resolve))
+            .collect()
+    }
+}
+
+impl Object for Primitive {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, "{}", self)
+    }
+}
+impl Object for i32 {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, "{}", self)
+    }
+}
+impl Object for f32 {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, "{}", self)
+    }
+}
+impl Object for bool {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, "{}", if *self {"true"} else {"false"})
+    }
+}
+impl Object for Dictionary {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, "<< ")?;
+        for (key, value) in &self.0 {
+            write!(out, "/{} ", key)?;
+            value.serialize(out)?;
+        }
+        write!(out, ">>")
+    }
+}
+impl<T: Object> Object for Option<T> {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        match *self {
+            Some (ref v) => v.serialize(out),
+            None => write!(out, "null"),
+        }
+    }
+}
+impl<T: Object> Object for Vec<T> {
+    fn serialize<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        write!(out, "[")?;
+        for e in self {
+            e.serialize(out)?;
+            write!(out, " ")?;
+        }
+        write!(out, "]")
+    }
+}
+
 /* Objects */
 pub struct IndirectObject {
     pub id: ObjectId,
@@ -172,6 +279,81 @@ impl Primitive {
         }
     }
 
+    /// Writes a canonical encoding: sorted `Dictionary` keys, normalized numbers/strings, same byte output from any writer.
+    pub fn serialize_canonical<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        match *self {
+            Primitive::Null => write!(out, "null"),
+            Primitive::Integer (n) => write!(out, "{}", n),
+            Primitive::Number (n) => write!(out, "{}", canonical_number(n)),
+            Primitive::Boolean (b) => write!(out, "{}", if b {"true"} else {"false"}),
+            Primitive::String (ref s) => {
+                write!(out, "(")?;
+                for &b in s {
+                    match b {
+                        b'(' | b')' | b'\\' => { write!(out, "\\{}", b as char)?; }
+                        _ => { out.write_all(&[b])?; }
+                    }
+                }
+                write!(out, ")")
+            }
+            Primitive::HexString (ref s) => {
+                write!(out, "<")?;
+                for &nibble in s {
+                    write!(out, "{:x}", nibble)?;
+                }
+                write!(out, ">")
+            }
+            Primitive::Name (ref name) => write!(out, "/{}", name),
+            Primitive::Reference (ObjectId {obj_nr, gen_nr}) => write!(out, "{} {} R", obj_nr, gen_nr),
+            Primitive::Array (ref a) => {
+                write!(out, "[")?;
+                for (i, e) in a.iter().enumerate() {
+                    if i > 0 {
+                        write!(out, " ")?;
+                    }
+                    e.serialize_canonical(out)?;
+                }
+                write!(out, "]")
+            }
+            Primitive::Dictionary (ref dict) => dict.serialize_canonical(out),
+            Primitive::Stream (ref stream) => {
+                stream.dictionary.serialize_canonical(out)?;
+                write!(out, "stream\n")?;
+                out.write_all(&stream.content)?;
+                write!(out, "\nendstream")
+            }
+        }
+    }
+}
+
+/// Renders `n` as a fixed-point decimal with no trailing zeros.
+fn canonical_number(n: f32) -> String {
+    if n == 0.0 {
+        return "0".to_string();
+    }
+    let mut s = format!("{:.6}", n);
+    while s.ends_with('0') {
+        s.pop();
+    }
+    if s.ends_with('.') {
+        s.pop();
+    }
+    s
+}
+
+
+impl Dictionary {
+    /// Like `Primitive::serialize_canonical`, but for a bare `Dictionary`: entries in key-byte order.
+    pub fn serialize_canonical<W: Write>(&self, out: &mut W) -> std::io::Result<()> {
+        let mut keys: Vec<&String> = self.0.keys().collect();
+        keys.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        write!(out, "<<")?;
+        for key in keys {
+            write!(out, "/{} ", key)?;
+            self.0[key].serialize_canonical(out)?;
+        }
+        write!(out, ">>")
+    }
 }
 
 
@@ -246,3 +428,77 @@ impl Display for Stream {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonical_dictionary_sorts_keys() {
+        let mut dict = Dictionary::default();
+        dict.set("Width", Primitive::Integer (1));
+        dict.set("Type", Primitive::Name ("Page".into()));
+        dict.set("Ascent", Primitive::Integer (2));
+
+        let mut out = Vec::new();
+        dict.serialize_canonical(&mut out).unwrap();
+        assert_eq!(out, b"<</Ascent 2/Type /Page/Width 1>>");
+    }
+
+    #[test]
+    fn canonical_hex_string_is_angle_bracketed() {
+        let p = Primitive::HexString (vec![0xa, 0xb, 0xc, 0xd]);
+        let mut out = Vec::new();
+        p.serialize_canonical(&mut out).unwrap();
+        assert_eq!(out, b"<abcd>");
+    }
+
+    struct NoResolve;
+    impl Resolve for NoResolve {
+        fn resolve(&self, _r: ObjectId) -> Result<Primitive> {
+            bail!("no references in this test")
+        }
+    }
+
+    // Mirrors what `#[derive(Object)]` generates for `struct Page { width: i32, height: Option<i32> }`.
+    struct Page { width: i32, height: Option<i32> }
+    impl FromPrimitive for Page {
+        fn from_primitive(p: Primitive, resolve: &Resolve) -> Result<Self> {
+            let mut dict = p.into_dictionary()?;
+            Ok(Page {
+                width: match dict.0.remove("Width") {
+                    Some(p) => FromPrimitive::from_primitive(p, resolve)?,
+                    None => bail!("Object Page, Key Width not found"),
+                },
+                height: match dict.0.remove("Height") {
+                    Some(p) => FromPrimitive::from_primitive(p, resolve)?,
+                    None => None,
+                },
+            })
+        }
+    }
+
+    #[test]
+    fn required_key_missing_is_an_error() {
+        let dict = Dictionary::default();
+        assert!(Page::from_primitive(Primitive::Dictionary (dict), &NoResolve).is_err());
+    }
+
+    #[test]
+    fn optional_key_missing_falls_back_to_none() {
+        let mut dict = Dictionary::default();
+        dict.set("Width", Primitive::Integer (10));
+        let page = Page::from_primitive(Primitive::Dictionary (dict), &NoResolve).unwrap();
+        assert_eq!(page.width, 10);
+        assert_eq!(page.height, None);
+    }
+
+    #[test]
+    fn optional_key_present_is_some() {
+        let mut dict = Dictionary::default();
+        dict.set("Width", Primitive::Integer (10));
+        dict.set("Height", Primitive::Integer (20));
+        let page = Page::from_primitive(Primitive::Dictionary (dict), &NoResolve).unwrap();
+        assert_eq!(page.height, Some (20));
+    }
+}
+
@@ -0,0 +1,598 @@
+//! Bridges `serde::Serialize`/`Deserialize` onto `Primitive`. Only built when the `serde` feature is enabled.
+#![cfg(feature = "serde")]
+
+use std;
+use std::fmt::{self, Display, Formatter};
+use serde::{ser, de};
+
+use file::object::{Primitive, Dictionary, ObjectId};
+
+/// Errors that can occur while converting between `Primitive` and a serde data type.
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// The `Primitive` was not of the kind the target type expected.
+    WrongType (&'static str),
+    /// An array/tuple had a different length than the target type required.
+    WrongLength (usize, usize),
+    /// A required key was missing - a struct field, or an enum-variant dictionary with no entries.
+    MissingKey (String),
+    /// The `Dictionary` had a key the target struct doesn't declare a field for.
+    UnexpectedKey (String),
+    /// Anything serde itself wants to say (via `ser::Error`/`de::Error`).
+    Custom (String),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match *self {
+            Error::WrongType (expected) => write!(f, "expected a {}", expected),
+            Error::WrongLength (expected, got) => write!(f, "expected {} elements, found {}", expected, got),
+            Error::MissingKey (ref key) => write!(f, "missing key {}", key),
+            Error::UnexpectedKey (ref key) => write!(f, "unexpected key {}", key),
+            Error::Custom (ref s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn description(&self) -> &str {
+        "primitive (de)serialization error"
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom (msg.to_string())
+    }
+}
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Custom (msg.to_string())
+    }
+    fn missing_field(field: &'static str) -> Self {
+        Error::MissingKey (field.to_string())
+    }
+    fn unknown_field(field: &str, _expected: &'static [&'static str]) -> Self {
+        Error::UnexpectedKey (field.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Turns any `T: Serialize` into a `Primitive`.
+pub fn to_primitive<T: ser::Serialize>(value: &T) -> Result<Primitive> {
+    value.serialize(Serializer)
+}
+
+/// Turns a `Primitive` back into any `T: Deserialize`.
+pub fn from_primitive<T: de::DeserializeOwned>(p: Primitive) -> Result<T> {
+    T::deserialize(Deserializer (p))
+}
+
+/// `serde::Serializer` impl mapping Rust values onto `Primitive`.
+pub struct Serializer;
+
+pub struct SeqSerializer (Vec<Primitive>);
+pub struct MapSerializer (Dictionary, Option<String>);
+pub struct StructSerializer (Dictionary);
+
+impl ser::Serializer for Serializer {
+    type Ok = Primitive;
+    type Error = Error;
+
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = SeqSerializer;
+    type SerializeMap = MapSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = StructSerializer;
+
+    fn serialize_bool(self, v: bool) -> Result<Primitive> { Ok(Primitive::Boolean (v)) }
+    fn serialize_i8(self, v: i8) -> Result<Primitive> { Ok(Primitive::Integer (v as i32)) }
+    fn serialize_i16(self, v: i16) -> Result<Primitive> { Ok(Primitive::Integer (v as i32)) }
+    fn serialize_i32(self, v: i32) -> Result<Primitive> { Ok(Primitive::Integer (v)) }
+    fn serialize_i64(self, v: i64) -> Result<Primitive> {
+        if v < i32::min_value() as i64 || v > i32::max_value() as i64 {
+            return Err(Error::WrongType ("i32 (value out of range)"));
+        }
+        Ok(Primitive::Integer (v as i32))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Primitive> { Ok(Primitive::Integer (v as i32)) }
+    fn serialize_u16(self, v: u16) -> Result<Primitive> { Ok(Primitive::Integer (v as i32)) }
+    fn serialize_u32(self, v: u32) -> Result<Primitive> {
+        if v > i32::max_value() as u32 {
+            return Err(Error::WrongType ("i32 (value out of range)"));
+        }
+        Ok(Primitive::Integer (v as i32))
+    }
+    fn serialize_u64(self, v: u64) -> Result<Primitive> {
+        if v > i32::max_value() as u64 {
+            return Err(Error::WrongType ("i32 (value out of range)"));
+        }
+        Ok(Primitive::Integer (v as i32))
+    }
+    fn serialize_f32(self, v: f32) -> Result<Primitive> { Ok(Primitive::Number (v)) }
+    fn serialize_f64(self, v: f64) -> Result<Primitive> { Ok(Primitive::Number (v as f32)) }
+    fn serialize_char(self, v: char) -> Result<Primitive> {
+        let mut s = String::new();
+        s.push(v);
+        Ok(Primitive::String (s.into_bytes()))
+    }
+    fn serialize_str(self, v: &str) -> Result<Primitive> {
+        Ok(Primitive::String (v.as_bytes().to_vec()))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<Primitive> {
+        Ok(Primitive::String (v.to_vec()))
+    }
+    fn serialize_none(self) -> Result<Primitive> { Ok(Primitive::Null) }
+    fn serialize_some<T: ?Sized + ser::Serialize>(self, value: &T) -> Result<Primitive> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<Primitive> { Ok(Primitive::Null) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Primitive> { Ok(Primitive::Null) }
+    fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<Primitive> {
+        Ok(Primitive::Name (variant.to_string()))
+    }
+    fn serialize_newtype_struct<T: ?Sized + ser::Serialize>(self, _name: &'static str, value: &T) -> Result<Primitive> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + ser::Serialize>(self, _name: &'static str, _index: u32, variant: &'static str, value: &T) -> Result<Primitive> {
+        let mut dict = Dictionary::default();
+        dict.set(variant, value.serialize(self)?);
+        Ok(Primitive::Dictionary (dict))
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer> {
+        Ok(SeqSerializer (Vec::with_capacity(len.unwrap_or(0))))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer> { self.serialize_seq(Some(len)) }
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer> { self.serialize_seq(Some(len)) }
+    fn serialize_tuple_variant(self, _name: &'static str, _index: u32, _variant: &'static str, len: usize) -> Result<SeqSerializer> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer> {
+        Ok(MapSerializer (Dictionary::default(), None))
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<StructSerializer> {
+        Ok(StructSerializer (Dictionary::default()))
+    }
+    fn serialize_struct_variant(self, _name: &'static str, _index: u32, _variant: &'static str, _len: usize) -> Result<StructSerializer> {
+        Ok(StructSerializer (Dictionary::default()))
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        self.0.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Primitive> { Ok(Primitive::Array (self.0)) }
+}
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_element<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Primitive> { ser::SerializeSeq::end(self) }
+}
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Primitive> { ser::SerializeSeq::end(self) }
+}
+impl ser::SerializeTupleVariant for SeqSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+    fn end(self) -> Result<Primitive> { ser::SerializeSeq::end(self) }
+}
+
+impl ser::SerializeMap for MapSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_key<T: ?Sized + ser::Serialize>(&mut self, key: &T) -> Result<()> {
+        let key = match key.serialize(Serializer)? {
+            Primitive::Name (s) => s,
+            Primitive::String (s) => String::from_utf8_lossy(&s).into_owned(),
+            _ => return Err(Error::WrongType ("map key")),
+        };
+        self.1 = Some(key);
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + ser::Serialize>(&mut self, value: &T) -> Result<()> {
+        let key = self.1.take().expect("serialize_value called before serialize_key");
+        self.0.set(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Primitive> { Ok(Primitive::Dictionary (self.0)) }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.0.set(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Primitive> { Ok(Primitive::Dictionary (self.0)) }
+}
+impl ser::SerializeStructVariant for StructSerializer {
+    type Ok = Primitive;
+    type Error = Error;
+    fn serialize_field<T: ?Sized + ser::Serialize>(&mut self, key: &'static str, value: &T) -> Result<()> {
+        self.0.set(key, value.serialize(Serializer)?);
+        Ok(())
+    }
+    fn end(self) -> Result<Primitive> { Ok(Primitive::Dictionary (self.0)) }
+}
+
+/// `serde::Deserializer` impl driven by the existing `type_str()`/`as_*` checks on `Primitive`.
+pub struct Deserializer (pub Primitive);
+
+macro_rules! forward_to_integer {
+    ($deserialize:ident, $visit:ident, $ty:ty) => {
+        fn $deserialize<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+            let n = self.0.as_integer().map_err(|_| Error::WrongType ("Integer"))?;
+            visitor.$visit(n as $ty)
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Null => visitor.visit_unit(),
+            Primitive::Boolean (b) => visitor.visit_bool(b),
+            Primitive::Integer (n) => visitor.visit_i32(n),
+            Primitive::Number (n) => visitor.visit_f32(n),
+            Primitive::String (s) | Primitive::HexString (s) => visitor.visit_byte_buf(s),
+            Primitive::Name (s) => visitor.visit_string(s),
+            Primitive::Array (a) => de::Deserializer::deserialize_seq(Deserializer (Primitive::Array (a)), visitor),
+            Primitive::Dictionary (d) => de::Deserializer::deserialize_map(Deserializer (Primitive::Dictionary (d)), visitor),
+            _ => Err(Error::WrongType ("value other than a Stream or Reference")),
+        }
+    }
+
+    forward_to_integer!(deserialize_i8, visit_i8, i8);
+    forward_to_integer!(deserialize_i16, visit_i16, i16);
+    forward_to_integer!(deserialize_i32, visit_i32, i32);
+    forward_to_integer!(deserialize_i64, visit_i64, i64);
+    forward_to_integer!(deserialize_u8, visit_u8, u8);
+    forward_to_integer!(deserialize_u16, visit_u16, u16);
+    forward_to_integer!(deserialize_u32, visit_u32, u32);
+    forward_to_integer!(deserialize_u64, visit_u64, u64);
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Boolean (b) => visitor.visit_bool(b),
+            _ => Err(Error::WrongType ("Boolean")),
+        }
+    }
+    fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Number (n) => visitor.visit_f32(n),
+            Primitive::Integer (n) => visitor.visit_f32(n as f32),
+            _ => Err(Error::WrongType ("Number")),
+        }
+    }
+    fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Number (n) => visitor.visit_f64(n as f64),
+            Primitive::Integer (n) => visitor.visit_f64(n as f64),
+            _ => Err(Error::WrongType ("Number")),
+        }
+    }
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Name (s) => visitor.visit_string(s),
+            Primitive::String (s) => visitor.visit_string(String::from_utf8_lossy(&s).into_owned()),
+            _ => Err(Error::WrongType ("Name or String")),
+        }
+    }
+    fn deserialize_bytes<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+    fn deserialize_byte_buf<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::String (s) | Primitive::HexString (s) => visitor.visit_byte_buf(s),
+            _ => Err(Error::WrongType ("String or HexString")),
+        }
+    }
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Null => visitor.visit_none(),
+            p => visitor.visit_some(Deserializer (p)),
+        }
+    }
+    fn deserialize_unit<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Null => visitor.visit_unit(),
+            _ => Err(Error::WrongType ("Null")),
+        }
+    }
+    fn deserialize_unit_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let items = self.0.into_array().map_err(|_| Error::WrongType ("Array"))?;
+        let len = items.len();
+        visitor.visit_seq(SeqDeserializer (items.into_iter(), len))
+    }
+    fn deserialize_tuple<V: de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        let items = self.0.into_array().map_err(|_| Error::WrongType ("Array"))?;
+        if items.len() != len {
+            return Err(Error::WrongLength (len, items.len()));
+        }
+        visitor.visit_seq(SeqDeserializer (items.into_iter(), len))
+    }
+    fn deserialize_tuple_struct<V: de::Visitor<'de>>(self, _name: &'static str, len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_tuple(len, visitor)
+    }
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        let dict = self.0.into_dictionary().map_err(|_| Error::WrongType ("Dictionary"))?;
+        visitor.visit_map(MapDeserializer (dict.0.into_iter(), None))
+    }
+    fn deserialize_struct<V: de::Visitor<'de>>(self, _name: &'static str, fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        let dict = self.0.into_dictionary().map_err(|_| Error::WrongType ("Dictionary"))?;
+        for key in dict.0.keys() {
+            if !fields.contains(&key.as_str()) {
+                return Err(Error::UnexpectedKey (key.clone()));
+            }
+        }
+        visitor.visit_map(MapDeserializer (dict.0.into_iter(), None))
+    }
+    fn deserialize_enum<V: de::Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::Name (s) => visitor.visit_enum(s.into_deserializer()),
+            Primitive::Dictionary (d) => visitor.visit_enum(EnumDeserializer (d)),
+            _ => Err(Error::WrongType ("Name or Dictionary")),
+        }
+    }
+    fn deserialize_identifier<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+    fn deserialize_ignored_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_any(visitor)
+    }
+    fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match self.0 {
+            Primitive::String (s) if s.len() == 1 => visitor.visit_char(s[0] as char),
+            _ => Err(Error::WrongType ("one-byte String")),
+        }
+    }
+    fn is_human_readable(&self) -> bool { false }
+}
+
+pub struct SeqDeserializer (std::vec::IntoIter<Primitive>, usize);
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer {
+    type Error = Error;
+    fn next_element_seed<T: de::DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        match self.0.next() {
+            Some(p) => { self.1 -= 1; seed.deserialize(Deserializer (p)).map(Some) }
+            None => Ok(None),
+        }
+    }
+    fn size_hint(&self) -> Option<usize> { Some(self.1) }
+}
+
+pub struct MapDeserializer (std::collections::hash_map::IntoIter<String, Primitive>, Option<Primitive>);
+
+impl<'de> de::MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.0.next() {
+            Some((k, v)) => {
+                self.1 = Some(v);
+                seed.deserialize(Primitive::Name (k).into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self.1.take().expect("next_value_seed called before next_key_seed");
+        seed.deserialize(Deserializer (value))
+    }
+}
+
+pub struct EnumDeserializer (Dictionary);
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = Deserializer;
+    fn variant_seed<V: de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Deserializer)> {
+        let mut iter = self.0.0.into_iter();
+        let (key, value) = iter.next().ok_or_else(|| Error::MissingKey ("<variant>".into()))?;
+        let variant = seed.deserialize(Primitive::Name (key).into_deserializer())?;
+        Ok((variant, Deserializer (value)))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Deserializer {
+    type Error = Error;
+    fn unit_variant(self) -> Result<()> { Ok(()) }
+    fn newtype_variant_seed<T: de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value> {
+        seed.deserialize(self)
+    }
+    fn tuple_variant<V: de::Visitor<'de>>(self, len: usize, visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+    fn struct_variant<V: de::Visitor<'de>>(self, fields: &'static [&'static str], visitor: V) -> Result<V::Value> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+use serde::de::IntoDeserializer;
+
+impl<'de> IntoDeserializer<'de, Error> for Primitive {
+    type Deserializer = Deserializer;
+    fn into_deserializer(self) -> Deserializer {
+        Deserializer (self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Page { width: i32 }
+
+    impl<'de> de::Deserialize<'de> for Page {
+        fn deserialize<D: de::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+            struct PageVisitor;
+            impl<'de> de::Visitor<'de> for PageVisitor {
+                type Value = Page;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "a Page dictionary")
+                }
+                fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> std::result::Result<Page, A::Error> {
+                    let mut width = None;
+                    while let Some(key) = map.next_key::<String>()? {
+                        match key.as_str() {
+                            "Width" => width = Some(map.next_value()?),
+                            _ => { let _: de::IgnoredAny = map.next_value()?; }
+                        }
+                    }
+                    let width = width.ok_or_else(|| de::Error::missing_field("Width"))?;
+                    Ok(Page {width})
+                }
+            }
+            d.deserialize_struct("Page", &["Width"], PageVisitor)
+        }
+    }
+
+    #[test]
+    fn missing_struct_field_is_missing_key() {
+        let dict = Dictionary::default();
+        let err = from_primitive::<Page>(Primitive::Dictionary (dict)).unwrap_err();
+        match err {
+            Error::MissingKey (ref key) => assert_eq!(key, "Width"),
+            _ => panic!("expected MissingKey, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn unknown_struct_field_is_unexpected_key() {
+        let mut dict = Dictionary::default();
+        dict.set("Width", Primitive::Integer (1));
+        dict.set("Bogus", Primitive::Integer (0));
+        let err = from_primitive::<Page>(Primitive::Dictionary (dict)).unwrap_err();
+        match err {
+            Error::UnexpectedKey (ref key) => assert_eq!(key, "Bogus"),
+            _ => panic!("expected UnexpectedKey, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn known_fields_deserialize_normally() {
+        let mut dict = Dictionary::default();
+        dict.set("Width", Primitive::Integer (42));
+        let page = from_primitive::<Page>(Primitive::Dictionary (dict)).unwrap();
+        assert_eq!(page.width, 42);
+    }
+
+    #[test]
+    fn out_of_range_u64_is_rejected_not_truncated() {
+        let err = to_primitive(&u64::max_value()).unwrap_err();
+        match err {
+            Error::WrongType (_) => {}
+            _ => panic!("expected WrongType, got {:?}", err),
+        }
+    }
+
+    #[test]
+    fn in_range_u64_serializes_to_integer() {
+        match to_primitive(&42u64).unwrap() {
+            Primitive::Integer (n) => assert_eq!(n, 42),
+            p => panic!("expected Integer, got {:?}", p),
+        }
+    }
+
+    #[test]
+    fn seq_round_trips_through_array() {
+        let values = vec![1, 2, 3];
+        let p = to_primitive(&values).unwrap();
+        match p {
+            Primitive::Array (ref a) => assert_eq!(a.len(), 3),
+            ref p => panic!("expected Array, got {:?}", p),
+        }
+        let back: Vec<i32> = from_primitive(p).unwrap();
+        assert_eq!(back, values);
+    }
+
+    #[test]
+    fn map_round_trips_through_dictionary() {
+        let mut map = std::collections::HashMap::new();
+        map.insert("Width".to_string(), 10);
+        map.insert("Height".to_string(), 20);
+        let p = to_primitive(&map).unwrap();
+        match p {
+            Primitive::Dictionary (_) => {}
+            ref p => panic!("expected Dictionary, got {:?}", p),
+        }
+        let back: std::collections::HashMap<String, i32> = from_primitive(p).unwrap();
+        assert_eq!(back, map);
+    }
+
+    enum Kind { Page, Image }
+
+    impl ser::Serialize for Kind {
+        fn serialize<S: ser::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            match *self {
+                Kind::Page => serializer.serialize_unit_variant("Kind", 0, "Page"),
+                Kind::Image => serializer.serialize_unit_variant("Kind", 1, "Image"),
+            }
+        }
+    }
+
+    impl<'de> de::Deserialize<'de> for Kind {
+        fn deserialize<D: de::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+            struct KindVisitor;
+            impl<'de> de::Visitor<'de> for KindVisitor {
+                type Value = Kind;
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "a Kind name")
+                }
+                fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> std::result::Result<Kind, A::Error> {
+                    let (kind, variant): (String, _) = data.variant()?;
+                    de::VariantAccess::unit_variant(variant)?;
+                    match kind.as_str() {
+                        "Page" => Ok(Kind::Page),
+                        "Image" => Ok(Kind::Image),
+                        other => Err(de::Error::custom(format!("unknown variant {}", other))),
+                    }
+                }
+            }
+            d.deserialize_enum("Kind", &["Page", "Image"], KindVisitor)
+        }
+    }
+
+    #[test]
+    fn unit_variant_round_trips_through_name() {
+        let p = to_primitive(&Kind::Image).unwrap();
+        match p {
+            Primitive::Name (ref s) => assert_eq!(s, "Image"),
+            ref p => panic!("expected Name, got {:?}", p),
+        }
+        match from_primitive::<Kind>(p).unwrap() {
+            Kind::Image => {}
+            Kind::Page => panic!("expected Image"),
+        }
+    }
+}